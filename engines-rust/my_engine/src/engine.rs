@@ -1,5 +1,10 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::board::{Board, Color, Move};
+use crate::ordering::OrderingTables;
 use crate::tt::TranspositionTable;
-use crate::utils::{Board, Move, now_ms};
+use crate::utils::now_ms;
 use crate::search;
 
 pub struct TimeControl {
@@ -10,53 +15,92 @@ pub struct TimeControl {
     pub movestogo: Option<i32>,
 }
 
+impl TimeControl {
+    /// Soft time budget for the side to move, roughly `remaining / max(movestogo, 30) + inc`.
+    /// Returns `None` when no clock was given (e.g. analysis mode).
+    pub fn soft_budget_ms(&self, side_to_move: Color) -> Option<u128> {
+        let (time, inc) = match side_to_move {
+            Color::White => (self.wtime?, self.winc.unwrap_or(0)),
+            Color::Black => (self.btime?, self.binc.unwrap_or(0)),
+        };
+        let movestogo = self.movestogo.unwrap_or(30).max(30) as i64;
+        let budget = time / movestogo + inc;
+        Some(budget.max(0) as u128)
+    }
+}
+
 pub struct Stats {
     pub nodes: u64,
     pub qnodes: u64,
 }
 
-pub struct StopToken {
-    stopped: bool,
+/// Per-thread search state. Every Lazy SMP worker gets its own move-ordering
+/// tables and node counters, but shares the transposition table and stop
+/// flag with every other worker searching the same `go`.
+pub struct Worker {
+    pub tt: Arc<TranspositionTable>,
+    pub stop: Arc<AtomicBool>,
+    pub stats: Stats,
+    pub ordering: OrderingTables,
+    pub deadline: Option<u128>,
 }
 
-impl StopToken {
-    pub fn new() -> Self { StopToken { stopped: false } }
-    pub fn is_set(&self) -> bool { self.stopped }
-    pub fn set(&mut self) { self.stopped = true; }
-    pub fn reset(&mut self) { self.stopped = false; }
+impl Worker {
+    pub fn new(tt: Arc<TranspositionTable>, stop: Arc<AtomicBool>) -> Self {
+        Worker {
+            tt,
+            stop,
+            stats: Stats { nodes: 0, qnodes: 0 },
+            ordering: OrderingTables::new(),
+            deadline: None,
+        }
+    }
+
+    pub fn should_stop(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Sets the shared stop flag once `deadline` has passed. Called every
+    /// few thousand nodes from search rather than on every node, to keep
+    /// the `now_ms()` syscall off the hot path.
+    pub fn check_time(&mut self) {
+        if let Some(deadline) = self.deadline {
+            if now_ms() >= deadline {
+                self.stop.store(true, Ordering::Relaxed);
+            }
+        }
+    }
 }
 
 pub struct Engine {
-    pub tt: TranspositionTable,
-    pub stop_token: StopToken,
-    pub stats: Stats,
+    pub tt: Arc<TranspositionTable>,
+    pub stop: Arc<AtomicBool>,
+    pub threads: usize,
 }
 
-pub type EngineRef = Engine;
-
 impl Engine {
     pub fn new(tt_mb: usize) -> Self {
         Engine {
-            tt: TranspositionTable::new(tt_mb),
-            stop_token: StopToken::new(),
-            stats: Stats { nodes: 0, qnodes: 0 },
+            tt: Arc::new(TranspositionTable::new(tt_mb)),
+            stop: Arc::new(AtomicBool::new(false)),
+            threads: 1,
         }
     }
 
-    pub fn choose_move(&mut self, board: &mut Board, _tc: &TimeControl) -> Move {
-        self.stop_token.reset();
-        self.stats.nodes = 0;
-        let start = now_ms();
-        let (_score, best_move) = search::search_root(board, self);
-        if let Some(mv) = best_move {
-            mv
-        } else {
-            // fallback
-            board.legal_moves().get(0).unwrap().clone()
-        }
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
     }
 
-    pub fn should_stop(&self) -> bool {
-        self.stop_token.is_set()
+    /// Sets the stop flag shared by every worker of the current `go`. The
+    /// UCI loop runs `go` to completion before it reads the next command,
+    /// so a `stop` sent during a search is only handled after it returned.
+    pub fn request_stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn choose_move(&mut self, board: &Board, tc: &TimeControl) -> Move {
+        self.stop.store(false, Ordering::Relaxed);
+        self.tt.new_search();
+        search::search_lazy_smp(board, self, tc)
     }
 }
@@ -0,0 +1,92 @@
+//! Static evaluation used by quiescence search and negamax leaves: tapered
+//! material + piece-square-table scoring, blended by the actual game phase.
+
+use crate::board::{Board, Color, PieceType};
+use crate::pst;
+
+/// Phase weight per piece type, indexed by `PieceType::index()`. Pawns and
+/// kings don't affect phase; the rest sum towards `pst::MAX_PHASE`, which a
+/// full set of minor/major pieces reaches exactly (4*1 + 4*1 + 4*2 + 2*4 = 24).
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// Computes the game phase from the material actually on the board, clamped
+/// to `MAX_PHASE` — near 24 in the opening, near 0 in bare-king endgames.
+pub fn game_phase(board: &Board) -> i32 {
+    let mut phase = 0;
+    for pt in PieceType::ALL {
+        let weight = PHASE_WEIGHTS[pt.index()];
+        if weight == 0 {
+            continue;
+        }
+        let count = board.pieces[Color::White.index()][pt.index()].count_ones()
+            + board.pieces[Color::Black.index()][pt.index()].count_ones();
+        phase += weight * count as i32;
+    }
+    phase.min(pst::MAX_PHASE)
+}
+
+/// Piece-square tables are defined from White's point of view; mirror the
+/// square vertically to look a black piece up in the same table.
+fn mirror(sq: u8) -> u8 {
+    sq ^ 56
+}
+
+/// Accumulates midgame and endgame material + PST sums from White's
+/// perspective, blends them by `game_phase`, and returns the score from the
+/// side-to-move's perspective so it plugs directly into negamax.
+pub fn evaluate(board: &Board) -> i32 {
+    let mut mg = 0;
+    let mut eg = 0;
+
+    for pt in PieceType::ALL {
+        let mg_table = pst::mg_table(pt);
+        let eg_table = pst::eg_table(pt);
+
+        let mut white = board.pieces[Color::White.index()][pt.index()];
+        while white != 0 {
+            let sq = white.trailing_zeros() as usize;
+            white &= white - 1;
+            mg += pst::mg_value(pt) + mg_table[sq];
+            eg += pst::eg_value(pt) + eg_table[sq];
+        }
+
+        let mut black = board.pieces[Color::Black.index()][pt.index()];
+        while black != 0 {
+            let sq = black.trailing_zeros() as u8;
+            black &= black - 1;
+            let sq = mirror(sq) as usize;
+            mg -= pst::mg_value(pt) + mg_table[sq];
+            eg -= pst::eg_value(pt) + eg_table[sq];
+        }
+    }
+
+    let score = pst::tapered_score(mg, eg, game_phase(board));
+    match board.side_to_move {
+        Color::White => score,
+        Color::Black => -score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn game_phase_is_max_at_startpos_and_zero_with_bare_kings() {
+        assert_eq!(game_phase(&Board::new()), pst::MAX_PHASE);
+
+        let bare_kings = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(game_phase(&bare_kings), 0);
+    }
+
+    #[test]
+    fn evaluate_flips_sign_with_side_to_move() {
+        // White is up a queen; the same position with the other side to
+        // move should score as exactly the negation, not a different value.
+        let white_to_move = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1");
+        let black_to_move = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1");
+        assert_eq!(evaluate(&white_to_move), -evaluate(&black_to_move));
+        assert!(evaluate(&white_to_move) > 0);
+    }
+}
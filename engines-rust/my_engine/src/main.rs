@@ -1,3 +1,4 @@
+mod board;
 mod engine;
 mod evaluator;
 mod ordering;
@@ -6,6 +7,7 @@ mod search;
 mod tt;
 mod uci;
 mod utils;
+mod zobrist;
 
 use uci::main_loop;
 
@@ -1,10 +1,146 @@
-use crate::utils::{Board, Move};
+use crate::board::{Board, Move, PieceType};
 
-pub fn order_moves(board: &Board, _tt_move: Option<&Move>) -> Vec<Move> {
-    // Return board.legal_moves() as-is for now
-    board.legal_moves()
+/// Deepest ply the killer table tracks; search should never recurse past this.
+pub const MAX_PLY: usize = 128;
+
+const TT_MOVE_BONUS: i32 = 1_000_000;
+const CAPTURE_BASE: i32 = 100_000;
+const KILLER_BONUS: i32 = 90_000;
+
+fn piece_value(pt: PieceType) -> i32 {
+    match pt {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 20_000,
+    }
+}
+
+/// Classic MVV-LVA score for a capture: `victim_value * 16 - attacker_value`,
+/// so e.g. pawn-takes-queen outranks queen-takes-pawn. Non-captures score 0.
+pub fn mvv_lva_score(board: &Board, mv: &Move) -> i32 {
+    if !mv.is_capture {
+        return 0;
+    }
+    let attacker = board.piece_at(mv.from).map(|(_, pt)| pt).unwrap_or(PieceType::Pawn);
+    let victim = if mv.is_en_passant {
+        PieceType::Pawn
+    } else {
+        board.piece_at(mv.to).map(|(_, pt)| pt).unwrap_or(PieceType::Pawn)
+    };
+    piece_value(victim) * 16 - piece_value(attacker)
+}
+
+/// Killer-move and history-heuristic tables carried across a single search.
+pub struct OrderingTables {
+    killers: Vec<[Option<Move>; 2]>,
+    history: [[i32; 64]; 64],
 }
 
-pub fn mvv_lva_score(_board: &Board, _mv: &Move) -> i32 {
-    0
+impl OrderingTables {
+    pub fn new() -> Self {
+        OrderingTables {
+            killers: vec![[None, None]; MAX_PLY],
+            history: [[0; 64]; 64],
+        }
+    }
+
+    /// Records a quiet move that caused a beta cutoff at `ply`, keeping the
+    /// two most recent distinct killers.
+    pub fn record_killer(&mut self, ply: usize, mv: Move) {
+        if mv.is_capture {
+            return;
+        }
+        let slot = &mut self.killers[ply.min(MAX_PLY - 1)];
+        if slot[0] != Some(mv) {
+            slot[1] = slot[0];
+            slot[0] = Some(mv);
+        }
+    }
+
+    /// Bumps the history score for a quiet move that caused a beta cutoff,
+    /// weighted by the remaining depth so deeper cutoffs count for more.
+    pub fn record_history(&mut self, mv: &Move, depth: i32) {
+        if mv.is_capture {
+            return;
+        }
+        self.history[mv.from as usize][mv.to as usize] += depth * depth;
+    }
+
+    fn killers_at(&self, ply: usize) -> [Option<Move>; 2] {
+        self.killers[ply.min(MAX_PLY - 1)]
+    }
+
+    fn history_score(&self, mv: &Move) -> i32 {
+        self.history[mv.from as usize][mv.to as usize]
+    }
+}
+
+/// Orders `moves` descending by: the TT move first, then captures by
+/// MVV-LVA (winning and losing captures alike), then killer moves, then
+/// quiets by history score.
+pub fn order_moves(board: &Board, moves: Vec<Move>, tt_move: Option<Move>, tables: &OrderingTables, ply: usize) -> Vec<Move> {
+    let killers = tables.killers_at(ply);
+    let mut scored: Vec<(i32, Move)> = moves
+        .into_iter()
+        .map(|mv| {
+            let score = if Some(mv) == tt_move {
+                TT_MOVE_BONUS
+            } else if mv.is_capture {
+                CAPTURE_BASE + mvv_lva_score(board, &mv)
+            } else if killers[0] == Some(mv) {
+                KILLER_BONUS + 1
+            } else if killers[1] == Some(mv) {
+                KILLER_BONUS
+            } else {
+                tables.history_score(&mv)
+            };
+            (score, mv)
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, mv)| mv).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn mvv_lva_favors_cheap_attacker_on_expensive_victim() {
+        // Pawn takes queen should outrank queen takes pawn.
+        let board_pxq = Board::from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1");
+        let pawn_takes_queen = board_pxq.find_uci_move("e4d5").unwrap();
+        let board_qxp = Board::from_fen("4k3/8/8/3p4/4Q3/8/8/4K3 w - - 0 1");
+        let queen_takes_pawn = board_qxp.find_uci_move("e4d5").unwrap();
+
+        assert!(mvv_lva_score(&board_pxq, &pawn_takes_queen) > mvv_lva_score(&board_qxp, &queen_takes_pawn));
+    }
+
+    #[test]
+    fn mvv_lva_score_is_zero_for_quiet_moves() {
+        let board = Board::new();
+        let quiet = board.find_uci_move("e2e4").unwrap();
+        assert_eq!(mvv_lva_score(&board, &quiet), 0);
+    }
+
+    #[test]
+    fn order_moves_puts_tt_move_first_then_captures_then_killers() {
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1");
+        let moves = board.legal_moves();
+        let capture = moves.iter().copied().find(|mv| mv.is_capture).unwrap();
+        let quiet = moves.iter().copied().find(|mv| !mv.is_capture && mv.to != capture.to).unwrap();
+
+        let mut tables = OrderingTables::new();
+        tables.record_killer(0, quiet);
+
+        let tt_move = moves.iter().copied().find(|&mv| mv != capture && mv != quiet).unwrap();
+        let ordered = order_moves(&board, moves, Some(tt_move), &tables, 0);
+
+        assert_eq!(ordered[0], tt_move);
+        assert!(ordered.iter().position(|&mv| mv == capture).unwrap() < ordered.iter().position(|&mv| mv == quiet).unwrap());
+    }
 }
@@ -1,9 +1,107 @@
-pub fn game_phase(_board_repr: &str) -> i32 {
-    // Simplified phase
-    12
+//! Midgame/endgame piece-square tables and material values.
+//!
+//! Tables are generated once from simple, well-understood heuristics
+//! (central control, pawn advancement, king safety vs. activity) rather
+//! than hand-typed as literal arrays, using the same lazily-built-once
+//! pattern as the attack tables in `board`.
+
+use std::sync::OnceLock;
+
+use crate::board::{file_of, rank_of, PieceType};
+
+pub const MAX_PHASE: i32 = 24;
+
+pub fn mg_value(pt: PieceType) -> i32 {
+    match pt {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 0,
+    }
+}
+
+pub fn eg_value(pt: PieceType) -> i32 {
+    match pt {
+        PieceType::Pawn => 120,
+        PieceType::Knight => 300,
+        PieceType::Bishop => 320,
+        PieceType::Rook => 530,
+        PieceType::Queen => 920,
+        PieceType::King => 0,
+    }
+}
+
+/// Taxicab distance from a square to the nearest of the four center
+/// squares (d4/d5/e4/e5), 0 there rising to 6 in the corners.
+fn dist_to_center(file: u8, rank: u8) -> i32 {
+    let fc = if file <= 3 { 3 - file as i32 } else { file as i32 - 4 };
+    let rc = if rank <= 3 { 3 - rank as i32 } else { rank as i32 - 4 };
+    fc + rc
+}
+
+fn build_table(f: impl Fn(u8, u8) -> i32) -> [i32; 64] {
+    let mut table = [0i32; 64];
+    for sq in 0..64u8 {
+        table[sq as usize] = f(file_of(sq), rank_of(sq));
+    }
+    table
+}
+
+struct PieceSquareTables {
+    mg: [[i32; 64]; 6],
+    eg: [[i32; 64]; 6],
+}
+
+fn build_tables() -> PieceSquareTables {
+    let pawn_mg = build_table(|file, rank| {
+        let central = (3 - (file as i32 - 3).abs().min((file as i32 - 4).abs())).max(0);
+        rank as i32 * 6 + central * 4
+    });
+    let pawn_eg = build_table(|_file, rank| rank as i32 * 12);
+
+    let knight_mg = build_table(|file, rank| (6 - dist_to_center(file, rank)) * 6);
+    let knight_eg = knight_mg;
+
+    let bishop_mg = build_table(|file, rank| (6 - dist_to_center(file, rank)) * 4);
+    let bishop_eg = bishop_mg;
+
+    let rook_mg = build_table(|file, rank| {
+        let seventh_rank = if rank == 6 { 20 } else { 0 };
+        let central_file = if file == 3 || file == 4 { 5 } else { 0 };
+        seventh_rank + central_file
+    });
+    let rook_eg = build_table(|_file, rank| if rank == 6 { 10 } else { 0 });
+
+    let queen_mg = build_table(|file, rank| (6 - dist_to_center(file, rank)) * 2);
+    let queen_eg = queen_mg;
+
+    let king_mg = build_table(|file, rank| {
+        let stay_back = if rank <= 1 { 10 } else { -10 * (rank as i32 - 1) };
+        stay_back + dist_to_center(file, rank) * 2
+    });
+    let king_eg = build_table(|file, rank| (6 - dist_to_center(file, rank)) * 8);
+
+    PieceSquareTables {
+        mg: [pawn_mg, knight_mg, bishop_mg, rook_mg, queen_mg, king_mg],
+        eg: [pawn_eg, knight_eg, bishop_eg, rook_eg, queen_eg, king_eg],
+    }
+}
+
+fn tables() -> &'static PieceSquareTables {
+    static TABLES: OnceLock<PieceSquareTables> = OnceLock::new();
+    TABLES.get_or_init(build_tables)
+}
+
+pub fn mg_table(pt: PieceType) -> &'static [i32; 64] {
+    &tables().mg[pt.index()]
+}
+
+pub fn eg_table(pt: PieceType) -> &'static [i32; 64] {
+    &tables().eg[pt.index()]
 }
 
 pub fn tapered_score(mg: i32, eg: i32, phase: i32) -> i32 {
-    const MAX_PHASE: i32 = 24;
     (phase * mg + (MAX_PHASE - phase) * eg) / MAX_PHASE
 }
@@ -1,20 +1,279 @@
-use crate::utils::{Board, Move};
-use crate::utils;
-use crate::engine::EngineRef;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
 
-pub fn search_root(board: &Board, eng: &EngineRef) -> (i32, Option<Move>) {
-    // Very small stub: pick the first legal move
-    let moves = board.legal_moves();
+use crate::board::{Board, Move};
+use crate::engine::{Engine, TimeControl, Worker};
+use crate::evaluator;
+use crate::ordering;
+use crate::tt::{self, PreFetchable, TTEntry};
+use crate::utils::{elapsed_ms, nps, now_ms, INF, MATE};
+
+/// How often (in nodes) to poll the clock. Cheap enough to not show up in
+/// profiles, frequent enough to keep response to `stop` snappy.
+const TIME_CHECK_INTERVAL: u64 = 2048;
+const MAX_ITERATIVE_DEPTH: i32 = 64;
+const MAX_QUIESCENCE_PLY: i32 = 32;
+
+/// Spawns `engine.threads` Lazy SMP workers, each iterative-deepening over
+/// its own cloned `Board` but sharing one transposition table and stop flag.
+/// Workers start a few plies apart so their search trees diversify; the
+/// move reported is whichever worker reached the greatest depth. Only the
+/// first worker emits per-iteration UCI `info` lines, to keep the output
+/// readable, but the final `info` line (printed here, right before the
+/// caller reports `bestmove`) always comes from whichever worker actually
+/// won, so the PV on that last line matches the move that gets played even
+/// when the winner isn't the reporting worker.
+pub fn search_lazy_smp(board: &Board, engine: &Engine, tc: &TimeControl) -> Move {
+    let start = now_ms();
+    let soft_budget = tc.soft_budget_ms(board.side_to_move);
+    let deadline = soft_budget.map(|budget| start + budget);
+    let threads = engine.threads.max(1);
+
+    let results: Vec<(i32, i32, Option<Move>, u64)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let mut worker_board = board.clone();
+                let mut worker = Worker::new(Arc::clone(&engine.tt), Arc::clone(&engine.stop));
+                worker.deadline = deadline;
+                let start_depth = 1 + (i as i32 % 3);
+                let report = i == 0;
+                scope.spawn(move || {
+                    iterative_deepening(&mut worker_board, &mut worker, start, start_depth, soft_budget, report)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    engine.stop.store(true, Ordering::Relaxed);
+
+    let winner = results
+        .into_iter()
+        .filter(|(_, _, mv, _)| mv.is_some())
+        .max_by_key(|(depth, _, _, _)| *depth);
+
+    let Some((depth, score, Some(best_move), nodes)) = winner else {
+        return board.legal_moves().remove(0);
+    };
+    let elapsed = elapsed_ms(start);
+    println!(
+        "info depth {} score cp {} nodes {} nps {} time {} hashfull {} pv {}",
+        depth, score, nodes, nps(nodes, elapsed), elapsed, engine.tt.hashfull(), best_move
+    );
+    best_move
+}
+
+/// Runs the iterative-deepening loop for a single worker, starting at
+/// `start_depth` and re-searching deeper until time runs out or the stop
+/// flag is set. Returns the greatest depth completed, its score, the best
+/// move found, and the worker's total node count.
+fn iterative_deepening(
+    board: &mut Board,
+    worker: &mut Worker,
+    start: u128,
+    start_depth: i32,
+    soft_budget: Option<u128>,
+    report: bool,
+) -> (i32, i32, Option<Move>, u64) {
+    let mut best_move = None;
+    let mut best_score = 0;
+    let mut depth_reached = 0;
+    let mut depth = start_depth.max(1);
+
+    while depth <= MAX_ITERATIVE_DEPTH {
+        let (score, mv, completed) = search_at_depth(board, depth, worker);
+        if let (true, Some(mv)) = (completed, mv) {
+            best_move = Some(mv);
+            best_score = score;
+            depth_reached = depth;
+            if report {
+                let elapsed = elapsed_ms(start);
+                println!(
+                    "info depth {} score cp {} nodes {} nps {} time {} hashfull {} pv {}",
+                    depth, best_score, worker.stats.nodes, nps(worker.stats.nodes, elapsed), elapsed,
+                    worker.tt.hashfull(), mv
+                );
+            }
+        }
+        if worker.should_stop() {
+            break;
+        }
+        if let Some(budget) = soft_budget {
+            if elapsed_ms(start) >= budget {
+                break;
+            }
+        }
+        depth += 1;
+    }
+    (depth_reached, best_score, best_move, worker.stats.nodes)
+}
+
+/// Runs one root-level negamax pass at `depth`. The third element of the
+/// result is whether the pass ran to completion; when it's `false` the
+/// search was aborted mid-iteration and `(score, mv)` reflect only a
+/// partially-searched move and must not replace a previous depth's result.
+fn search_at_depth(board: &mut Board, depth: i32, worker: &mut Worker) -> (i32, Option<Move>, bool) {
+    let tt_move = worker.tt.probe(board.hash).and_then(|entry| entry.best_move);
+    let moves = ordering::order_moves(board, board.legal_moves(), tt_move, &worker.ordering, 0);
     if moves.is_empty() {
-        return (0, None);
+        return (0, None, true);
+    }
+
+    let beta = INF;
+    let mut alpha = -INF;
+    let mut best_score = -INF;
+    let mut best_move = None;
+
+    for mv in moves {
+        worker.tt.prefetch(board.predict_hash_after(&mv));
+        board.push(&mv);
+        let score = -negamax(board, depth - 1, -beta, -alpha, worker, 1);
+        board.pop();
+
+        if worker.should_stop() {
+            return (best_score, best_move, false);
+        }
+
+        if score > best_score || best_move.is_none() {
+            best_score = score;
+            best_move = Some(mv);
+        }
+        if score > alpha {
+            alpha = score;
+        }
     }
-    (0, Some(moves[0].clone()))
+
+    worker.tt.store(board.hash, TTEntry {
+        depth,
+        score: best_score,
+        flag: tt::EXACT,
+        best_move,
+        age: worker.tt.generation(),
+    });
+    (best_score, best_move, true)
 }
 
-pub fn negamax(_board: &mut Board, _depth: i32, _alpha: i32, _beta: i32, _eng: &EngineRef, _ply: i32) -> i32 {
-    0
+pub fn negamax(board: &mut Board, depth: i32, mut alpha: i32, mut beta: i32, worker: &mut Worker, ply: i32) -> i32 {
+    worker.stats.nodes += 1;
+    if worker.stats.nodes.is_multiple_of(TIME_CHECK_INTERVAL) {
+        worker.check_time();
+    }
+    if worker.should_stop() {
+        return evaluator::evaluate(board);
+    }
+    if board.halfmove_clock >= 100 {
+        return 0;
+    }
+
+    let orig_alpha = alpha;
+    let mut tt_move = None;
+    if let Some(entry) = worker.tt.probe(board.hash) {
+        tt_move = entry.best_move;
+        if entry.depth >= depth {
+            match entry.flag {
+                tt::EXACT => return entry.score,
+                tt::LOWER if entry.score > alpha => alpha = entry.score,
+                tt::UPPER if entry.score < beta => beta = entry.score,
+                _ => {}
+            }
+            if alpha >= beta {
+                return entry.score;
+            }
+        }
+    }
+
+    if depth <= 0 {
+        return quiescence(board, alpha, beta, worker, 0);
+    }
+
+    let moves = ordering::order_moves(board, board.legal_moves(), tt_move, &worker.ordering, ply as usize);
+    if moves.is_empty() {
+        return if board.in_check(board.side_to_move) { -MATE + ply } else { 0 };
+    }
+
+    let mut best_score = -INF;
+    let mut best_move = None;
+    for mv in moves {
+        worker.tt.prefetch(board.predict_hash_after(&mv));
+        board.push(&mv);
+        let score = -negamax(board, depth - 1, -beta, -alpha, worker, ply + 1);
+        board.pop();
+
+        if worker.should_stop() {
+            return best_score.max(score);
+        }
+
+        if score > best_score {
+            best_score = score;
+            best_move = Some(mv);
+        }
+        if score > alpha {
+            alpha = score;
+        }
+        if alpha >= beta {
+            worker.ordering.record_killer(ply as usize, mv);
+            worker.ordering.record_history(&mv, depth);
+            break;
+        }
+    }
+
+    let flag = if best_score <= orig_alpha {
+        tt::UPPER
+    } else if best_score >= beta {
+        tt::LOWER
+    } else {
+        tt::EXACT
+    };
+    worker.tt.store(board.hash, TTEntry {
+        depth,
+        score: best_score,
+        flag,
+        best_move,
+        age: worker.tt.generation(),
+    });
+    best_score
 }
 
-pub fn quiescence(_board: &mut Board, _alpha: i32, _beta: i32, _eng: &EngineRef, _q_depth: i32) -> i32 {
-    0
+pub fn quiescence(board: &mut Board, mut alpha: i32, beta: i32, worker: &mut Worker, q_ply: i32) -> i32 {
+    worker.stats.nodes += 1;
+    worker.stats.qnodes += 1;
+    if worker.stats.nodes.is_multiple_of(TIME_CHECK_INTERVAL) {
+        worker.check_time();
+    }
+    if worker.should_stop() {
+        return evaluator::evaluate(board);
+    }
+
+    let stand_pat = evaluator::evaluate(board);
+    if stand_pat >= beta {
+        return stand_pat;
+    }
+    if stand_pat > alpha {
+        alpha = stand_pat;
+    }
+    if q_ply >= MAX_QUIESCENCE_PLY {
+        return stand_pat;
+    }
+
+    let mut captures: Vec<Move> = board.legal_moves().into_iter().filter(|mv| mv.is_capture).collect();
+    captures.sort_by_key(|mv| -ordering::mvv_lva_score(board, mv));
+
+    for mv in captures {
+        worker.tt.prefetch(board.predict_hash_after(&mv));
+        board.push(&mv);
+        let score = -quiescence(board, -beta, -alpha, worker, q_ply + 1);
+        board.pop();
+
+        if worker.should_stop() {
+            break;
+        }
+        if score >= beta {
+            return score;
+        }
+        if score > alpha {
+            alpha = score;
+        }
+    }
+    alpha
 }
@@ -1,5 +1,20 @@
-use std::collections::HashMap;
-use crate::utils::Move;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Mutex;
+
+use crate::board::Move;
+
+pub const EXACT: i32 = 0;
+pub const LOWER: i32 = 1;
+pub const UPPER: i32 = 2;
+
+/// Shard count for the lock-striped table: enough that concurrent
+/// Lazy SMP workers rarely hash into the same mutex.
+const NUM_SHARDS: usize = 16;
+const SHARD_BITS: u32 = 4; // log2(NUM_SHARDS)
+
+/// Rough per-slot footprint used only to size the table from `size_mb`;
+/// doesn't need to be exact, just in the right ballpark.
+const SLOT_BYTES: usize = 40;
 
 #[derive(Clone, Debug)]
 pub struct TTEntry {
@@ -10,30 +25,170 @@ pub struct TTEntry {
     pub age: i32,
 }
 
+#[derive(Clone)]
+struct Slot {
+    /// Upper bits of the full key, stored alongside the entry so a lookup
+    /// that maps to the same bucket by coincidence is detected as a miss
+    /// instead of silently returning someone else's entry.
+    checksum: u32,
+    entry: TTEntry,
+}
+
+struct Shard {
+    slots: Vec<Option<Slot>>,
+    mask: usize,
+}
+
+impl Shard {
+    fn new(num_slots: usize) -> Self {
+        let num_slots = num_slots.next_power_of_two().max(1);
+        Shard { slots: vec![None; num_slots], mask: num_slots - 1 }
+    }
+
+    fn slot_index(&self, key: u64) -> usize {
+        ((key >> SHARD_BITS) as usize) & self.mask
+    }
+}
+
+fn shard_of(key: u64) -> usize {
+    (key as usize) & (NUM_SHARDS - 1)
+}
+
+fn checksum_of(key: u64) -> u32 {
+    (key >> 32) as u32
+}
+
+/// Fixed-size, lock-striped transposition table shared by every Lazy SMP
+/// worker. Each shard is a flat power-of-two array indexed by `key`, with
+/// depth-and-generation replacement: a slot is only kept if the incumbent
+/// is from the current search generation and deeper than the new entry.
 pub struct TranspositionTable {
-    table: HashMap<u64, TTEntry>,
-    pub generation: i32,
+    shards: Vec<Mutex<Shard>>,
+    generation: AtomicI32,
 }
 
 impl TranspositionTable {
-    pub fn new(_size_mb: usize) -> Self {
-        TranspositionTable { table: HashMap::new(), generation: 0 }
+    pub fn new(size_mb: usize) -> Self {
+        let total_bytes = size_mb.max(1) * 1024 * 1024;
+        let total_slots = (total_bytes / SLOT_BYTES).max(NUM_SHARDS);
+        let per_shard = (total_slots / NUM_SHARDS).max(1);
+        let shards = (0..NUM_SHARDS).map(|_| Mutex::new(Shard::new(per_shard))).collect();
+        TranspositionTable { shards, generation: AtomicI32::new(0) }
     }
 
     pub fn probe(&self, key: u64) -> Option<TTEntry> {
-        self.table.get(&key).cloned()
+        let shard = self.shards[shard_of(key)].lock().unwrap();
+        let idx = shard.slot_index(key);
+        match &shard.slots[idx] {
+            Some(slot) if slot.checksum == checksum_of(key) => Some(slot.entry.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn store(&self, key: u64, entry: TTEntry) {
+        let current_generation = self.generation();
+        let mut shard = self.shards[shard_of(key)].lock().unwrap();
+        let idx = shard.slot_index(key);
+        let keep_incumbent = matches!(
+            &shard.slots[idx],
+            Some(existing) if existing.entry.age == current_generation && existing.entry.depth > entry.depth
+        );
+        if !keep_incumbent {
+            shard.slots[idx] = Some(Slot { checksum: checksum_of(key), entry });
+        }
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            for slot in shard.lock().unwrap().slots.iter_mut() {
+                *slot = None;
+            }
+        }
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn generation(&self) -> i32 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// UCI `hashfull`: permille of slots occupied by any entry, sampled
+    /// across every shard.
+    pub fn hashfull(&self) -> u32 {
+        let mut filled = 0usize;
+        let mut total = 0usize;
+        for shard in &self.shards {
+            let shard = shard.lock().unwrap();
+            total += shard.slots.len();
+            filled += shard.slots.iter().filter(|s| s.is_some()).count();
+        }
+        (filled * 1000).checked_div(total).unwrap_or(0) as u32
+    }
+}
+
+/// Issues a CPU prefetch hint for the slot a given key maps to, so the
+/// entry is in cache by the time `probe` actually reads it.
+pub trait PreFetchable {
+    fn prefetch(&self, key: u64);
+}
+
+impl PreFetchable for TranspositionTable {
+    fn prefetch(&self, key: u64) {
+        // `try_lock` rather than `lock`: a prefetch is a hint, not something
+        // worth blocking a search thread over if another worker holds the shard.
+        if let Ok(shard) = self.shards[shard_of(key)].try_lock() {
+            let idx = shard.slot_index(key);
+            let ptr = &shard.slots[idx] as *const Option<Slot>;
+            prefetch_read(ptr);
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+fn prefetch_read<T>(ptr: *const T) {
+    use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+    unsafe { _mm_prefetch(ptr as *const i8, _MM_HINT_T0) };
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn prefetch_read<T>(_ptr: *const T) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(depth: i32, age: i32) -> TTEntry {
+        TTEntry { depth, score: 0, flag: EXACT, best_move: None, age }
     }
 
-    pub fn store(&mut self, key: u64, entry: TTEntry) {
-        self.table.insert(key, entry);
+    #[test]
+    fn same_generation_keeps_deeper_entry_over_shallower() {
+        let tt = TranspositionTable::new(1);
+        let key = 0x1234;
+        tt.store(key, entry(5, tt.generation()));
+        tt.store(key, entry(3, tt.generation()));
+        assert_eq!(tt.probe(key).unwrap().depth, 5);
     }
 
-    pub fn clear(&mut self) {
-        self.table.clear();
-        self.generation += 1;
+    #[test]
+    fn same_generation_overwrites_with_deeper_entry() {
+        let tt = TranspositionTable::new(1);
+        let key = 0x1234;
+        tt.store(key, entry(3, tt.generation()));
+        tt.store(key, entry(5, tt.generation()));
+        assert_eq!(tt.probe(key).unwrap().depth, 5);
     }
 
-    pub fn new_search(&mut self) {
-        self.generation += 1;
+    #[test]
+    fn stale_generation_is_always_overwritten_regardless_of_depth() {
+        let tt = TranspositionTable::new(1);
+        let key = 0x1234;
+        tt.store(key, entry(10, tt.generation()));
+        tt.new_search();
+        tt.store(key, entry(1, tt.generation()));
+        assert_eq!(tt.probe(key).unwrap().depth, 1);
     }
 }
@@ -1,6 +1,6 @@
 use std::io::{self, Write};
+use crate::board::Board;
 use crate::engine::{Engine, TimeControl};
-use crate::utils::{Board};
 
 pub fn main_loop() {
     let mut engine = Engine::new(128);
@@ -17,25 +17,63 @@ pub fn main_loop() {
             "uci" => {
                 println!("id name MyEngine-Rust");
                 println!("id author KK");
+                println!("option name Threads type spin default 1 min 1 max 256");
                 println!("uciok");
             }
             "isready" => println!("readyok"),
             "ucinewgame" => { engine.tt.clear(); board = Board::new(); },
             "position" => {
-                // Very small support: "position startpos" or "position fen ..."
-                if parts.len() >= 2 && parts[1] == "startpos" {
+                // "position startpos [moves ...]" or "position fen <fen> [moves ...]"
+                let moves_token = parts.iter().position(|&p| p == "moves");
+                let setup_end = moves_token.unwrap_or(parts.len());
+                if parts.get(1) == Some(&"startpos") {
                     board = Board::new();
+                } else if parts.get(1) == Some(&"fen") {
+                    board = Board::from_fen(&parts[2..setup_end].join(" "));
+                }
+                if let Some(idx) = moves_token {
+                    for mv_str in &parts[idx + 1..] {
+                        if let Some(mv) = board.find_uci_move(mv_str) {
+                            board.push(&mv);
+                        }
+                    }
+                }
+            }
+            "setoption" => {
+                // "setoption name Threads value N"
+                if parts.get(1) == Some(&"name") && parts.get(2) == Some(&"Threads") {
+                    if let Some(value) = parts.get(4).and_then(|s| s.parse::<usize>().ok()) {
+                        engine.set_threads(value);
+                    }
                 }
             }
             "go" => {
-                let tc = TimeControl { wtime: None, btime: None, winc: None, binc: None, movestogo: None };
-                let mv = engine.choose_move(&mut board, &tc);
-                println!("bestmove {}", mv.0);
+                let tc = parse_time_control(&parts);
+                let mv = engine.choose_move(&board, &tc);
+                println!("bestmove {}", mv);
             }
-            "stop" => engine.stop_token.set(),
+            "stop" => engine.request_stop(),
             "quit" => break,
             _ => println!("# Unknown command: {}", parts[0]),
         }
         io::stdout().flush().ok();
     }
 }
+
+/// Parses the `wtime`/`btime`/`winc`/`binc`/`movestogo` tokens out of a `go` command.
+fn parse_time_control(parts: &[&str]) -> TimeControl {
+    let mut tc = TimeControl { wtime: None, btime: None, winc: None, binc: None, movestogo: None };
+    let mut i = 1;
+    while i < parts.len() {
+        match parts[i] {
+            "wtime" => { i += 1; tc.wtime = parts.get(i).and_then(|s| s.parse().ok()); }
+            "btime" => { i += 1; tc.btime = parts.get(i).and_then(|s| s.parse().ok()); }
+            "winc" => { i += 1; tc.winc = parts.get(i).and_then(|s| s.parse().ok()); }
+            "binc" => { i += 1; tc.binc = parts.get(i).and_then(|s| s.parse().ok()); }
+            "movestogo" => { i += 1; tc.movestogo = parts.get(i).and_then(|s| s.parse().ok()); }
+            _ => {}
+        }
+        i += 1;
+    }
+    tc
+}
@@ -0,0 +1,104 @@
+//! Zobrist key tables for incremental position hashing.
+//!
+//! Keys are generated from a fixed-seed PRNG (splitmix64) so that hashes
+//! are reproducible across runs and builds.
+
+use std::sync::OnceLock;
+
+use crate::board::{Color, PieceType, Square};
+
+const SEED: u64 = 0x9E3779B97F4A7C15;
+
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+pub struct ZobristKeys {
+    pub piece_square: [[u64; 64]; 12],
+    pub ep_file: [u64; 8],
+    pub castling: [u64; 16],
+    pub side_to_move: u64,
+}
+
+fn piece_square_index(color: Color, pt: PieceType) -> usize {
+    color.index() * 6 + pt.index()
+}
+
+fn build_keys() -> ZobristKeys {
+    let mut rng = SplitMix64::new(SEED);
+    let mut piece_square = [[0u64; 64]; 12];
+    for slot in piece_square.iter_mut() {
+        for key in slot.iter_mut() {
+            *key = rng.next();
+        }
+    }
+    let mut ep_file = [0u64; 8];
+    for key in ep_file.iter_mut() {
+        *key = rng.next();
+    }
+    let mut castling = [0u64; 16];
+    for key in castling.iter_mut() {
+        *key = rng.next();
+    }
+    let side_to_move = rng.next();
+    ZobristKeys { piece_square, ep_file, castling, side_to_move }
+}
+
+pub fn keys() -> &'static ZobristKeys {
+    static KEYS: OnceLock<ZobristKeys> = OnceLock::new();
+    KEYS.get_or_init(build_keys)
+}
+
+pub fn piece_key(color: Color, pt: PieceType, sq: Square) -> u64 {
+    keys().piece_square[piece_square_index(color, pt)][sq as usize]
+}
+
+pub fn ep_key(file: u8) -> u64 {
+    keys().ep_file[file as usize]
+}
+
+pub fn castling_key(rights: u8) -> u64 {
+    keys().castling[rights as usize]
+}
+
+pub fn side_to_move_key() -> u64 {
+    keys().side_to_move
+}
+
+/// Computes a position's hash from scratch. Used to seed `Board::hash` and,
+/// in debug assertions, to cross-check the incrementally maintained value.
+pub fn compute_hash(board: &crate::board::Board) -> u64 {
+    let mut hash = 0u64;
+    for color in [Color::White, Color::Black] {
+        for pt in PieceType::ALL {
+            let mut bb = board.pieces[color.index()][pt.index()];
+            while bb != 0 {
+                let sq = bb.trailing_zeros() as Square;
+                bb &= bb - 1;
+                hash ^= piece_key(color, pt, sq);
+            }
+        }
+    }
+    if let Some(sq) = board.ep_square {
+        hash ^= ep_key(sq % 8);
+    }
+    hash ^= castling_key(board.castling_rights);
+    if board.side_to_move == Color::Black {
+        hash ^= side_to_move_key();
+    }
+    hash
+}